@@ -2,17 +2,596 @@ use quad_gl::{QuadGl, Vertex};
 
 pub use quad_gl::{colors::*, Color, Image, Texture2D};
 
+/// A shared rectangle vocabulary for drawing, clipping and projection: an
+/// axis-aligned `x, y, w, h` rect. The methods treat `w`/`h` as non-negative
+/// extents; `new` and `scale` do not check or normalize them. `(x, y)` is
+/// the top-left corner in the same y-down screen space as `draw_rectangle`,
+/// `draw_text`, etc. — `y + h` is further *down* the screen, not up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect { x, y, w, h }
+    }
+
+    pub fn contains(&self, point: glam::Vec2) -> bool {
+        point.x >= self.x
+            && point.x <= self.x + self.w
+            && point.y >= self.y
+            && point.y <= self.y + self.h
+    }
+
+    pub fn overlaps(&self, other: Rect) -> bool {
+        self.x < other.x + other.w
+            && self.x + self.w > other.x
+            && self.y < other.y + other.h
+            && self.y + self.h > other.y
+    }
+
+    /// The overlapping region of `self` and `other`, or a zero-sized rect at
+    /// their nearest edge if they don't overlap.
+    pub fn intersect(&self, other: Rect) -> Rect {
+        let x0 = self.x.max(other.x);
+        let y0 = self.y.max(other.y);
+        let x1 = (self.x + self.w).min(other.x + other.w);
+        let y1 = (self.y + self.h).min(other.y + other.h);
+
+        Rect::new(x0, y0, (x1 - x0).max(0.), (y1 - y0).max(0.))
+    }
+
+    pub fn offset(&self, by: glam::Vec2) -> Rect {
+        Rect::new(self.x + by.x, self.y + by.y, self.w, self.h)
+    }
+
+    pub fn scale(&self, sx: f32, sy: f32) -> Rect {
+        Rect::new(self.x * sx, self.y * sy, self.w * sx, self.h * sy)
+    }
+}
+
 pub enum ScreenCoordinates {
-    Fixed(f32, f32, f32, f32),
+    /// A fixed world-space viewport in the same y-down convention as `Rect`
+    /// and `PixelPerfect`: `rect.y` is the top edge, `rect.y + rect.h` the
+    /// bottom edge. `Fixed(Rect::new(0., 0., w, h))` reproduces
+    /// `PixelPerfect`'s orientation for a `w x h` screen.
+    Fixed(Rect),
     PixelPerfect,
 }
 
+/// The shape a `Gradient` is projected onto before sampling its stops.
+#[derive(Debug, Clone, Copy)]
+pub enum GradientMode {
+    Linear { p0: glam::Vec2, p1: glam::Vec2 },
+    Radial { center: glam::Vec2, radius: f32 },
+}
+
+/// An ordered list of `(offset, Color)` stops sampled along a `GradientMode`.
+///
+/// Stops should be sorted by ascending offset in `[0, 1]`. Colors are
+/// interpolated in premultiplied-alpha space so fading-out stops don't bleed
+/// dark edges into neighbouring colors.
+#[derive(Debug, Clone)]
+pub struct Gradient {
+    pub stops: Vec<(f32, Color)>,
+    pub mode: GradientMode,
+}
+
+impl Gradient {
+    pub fn linear(p0: glam::Vec2, p1: glam::Vec2, stops: Vec<(f32, Color)>) -> Gradient {
+        Gradient {
+            stops,
+            mode: GradientMode::Linear { p0, p1 },
+        }
+    }
+
+    pub fn radial(center: glam::Vec2, radius: f32, stops: Vec<(f32, Color)>) -> Gradient {
+        Gradient {
+            stops,
+            mode: GradientMode::Radial { center, radius },
+        }
+    }
+
+    fn t_at(&self, point: glam::Vec2) -> f32 {
+        match self.mode {
+            GradientMode::Linear { p0, p1 } => {
+                let d = p1 - p0;
+                let denom = d.dot(d);
+                if denom < std::f32::EPSILON {
+                    0.
+                } else {
+                    ((point - p0).dot(d) / denom).clamp(0., 1.)
+                }
+            }
+            GradientMode::Radial { center, radius } => {
+                if radius < std::f32::EPSILON {
+                    0.
+                } else {
+                    ((point - center).length() / radius).clamp(0., 1.)
+                }
+            }
+        }
+    }
+
+    fn color_at(&self, point: glam::Vec2) -> Color {
+        sample_stops(&self.stops, self.t_at(point))
+    }
+}
+
+fn premultiply(color: Color) -> [f32; 4] {
+    let [r, g, b, a] = color.0;
+    [r * a, g * a, b * a, a]
+}
+
+fn unpremultiply(premultiplied: [f32; 4]) -> Color {
+    let [r, g, b, a] = premultiplied;
+    if a < std::f32::EPSILON {
+        Color([0., 0., 0., 0.])
+    } else {
+        Color([r / a, g / a, b / a, a])
+    }
+}
+
+/// Finds the stops bracketing `t` and lerps between them in premultiplied-alpha space.
+fn sample_stops(stops: &[(f32, Color)], t: f32) -> Color {
+    if stops.is_empty() {
+        return Color([0., 0., 0., 0.]);
+    }
+    if stops.len() == 1 {
+        return stops[0].1;
+    }
+
+    let mut lower = stops[0];
+    let mut upper = stops[stops.len() - 1];
+    for window in stops.windows(2) {
+        let (a, b) = (window[0], window[1]);
+        if t >= a.0 && t <= b.0 {
+            lower = a;
+            upper = b;
+            break;
+        }
+    }
+
+    let span = upper.0 - lower.0;
+    let local_t = if span.abs() < std::f32::EPSILON {
+        0.
+    } else {
+        ((t - lower.0) / span).clamp(0., 1.)
+    };
+
+    let a = premultiply(lower.1);
+    let b = premultiply(upper.1);
+    unpremultiply([
+        a[0] + (b[0] - a[0]) * local_t,
+        a[1] + (b[1] - a[1]) * local_t,
+        a[2] + (b[2] - a[2]) * local_t,
+        a[3] + (b[3] - a[3]) * local_t,
+    ])
+}
+
+/// Independent per-corner values for `draw_rectangle_rounded`, named the same
+/// way as the corners of the rectangle they apply to.
+#[derive(Debug, Clone, Copy)]
+pub struct Corners<T> {
+    pub top_left: T,
+    pub top_right: T,
+    pub bottom_right: T,
+    pub bottom_left: T,
+}
+
+impl<T: Copy> Corners<T> {
+    pub fn all(radius: T) -> Corners<T> {
+        Corners {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+}
+
+/// Builds the perimeter of a rounded rectangle, going clockwise from the top-left
+/// corner, tessellating each corner as a quarter-circle of `segments` steps.
+///
+/// Each radius is clamped to `min(w, h) / 2`, and a corner whose radius is ~0 is
+/// emitted as a single sharp point instead of an arc.
+fn rounded_rectangle_outline(
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+    radii: Corners<f32>,
+    segments: u32,
+) -> Vec<glam::Vec2> {
+    let max_radius = (w.min(h) / 2.).max(0.);
+    let tl = radii.top_left.clamp(0., max_radius);
+    let tr = radii.top_right.clamp(0., max_radius);
+    let br = radii.bottom_right.clamp(0., max_radius);
+    let bl = radii.bottom_left.clamp(0., max_radius);
+
+    let mut points = Vec::new();
+
+    let mut push_corner = |cx: f32, cy: f32, radius: f32, start_angle: f32| {
+        if radius < std::f32::EPSILON {
+            points.push(glam::Vec2::new(cx, cy));
+            return;
+        }
+        for i in 0..=segments {
+            let angle = start_angle + std::f32::consts::FRAC_PI_2 * (i as f32 / segments as f32);
+            points.push(glam::Vec2::new(
+                cx + radius * angle.cos(),
+                cy + radius * angle.sin(),
+            ));
+        }
+    };
+
+    push_corner(x + tl, y + tl, tl, std::f32::consts::PI);
+    push_corner(x + w - tr, y + tr, tr, std::f32::consts::PI * 1.5);
+    push_corner(x + w - br, y + h - br, br, 0.);
+    push_corner(x + bl, y + h - bl, bl, std::f32::consts::FRAC_PI_2);
+
+    points
+}
+
+const FONT_ATLAS_SIZE: u16 = 1024;
+
+/// Where a rasterized glyph landed in a `Font`'s atlas, plus the metrics needed
+/// to place it relative to the baseline.
+#[derive(Debug, Clone, Copy)]
+struct AtlasGlyph {
+    atlas_x: u16,
+    atlas_y: u16,
+    width: u16,
+    height: u16,
+    xmin: f32,
+    ymin: f32,
+    advance: f32,
+}
+
+/// Advance width and vertical line metrics for a run of shaped text, as
+/// returned by `Font::measure_text`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextMetrics {
+    pub width: f32,
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+}
+
+/// Shelf-packs rects of any size into a fixed `size x size` square atlas, starting
+/// a new shelf once the current row runs out of width.
+#[derive(Debug, Clone, Copy)]
+struct ShelfPacker {
+    size: u16,
+    cursor_x: u16,
+    cursor_y: u16,
+    shelf_height: u16,
+}
+
+impl ShelfPacker {
+    fn new(size: u16) -> ShelfPacker {
+        ShelfPacker {
+            size,
+            cursor_x: 0,
+            cursor_y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    /// Packs a `width x height` rect, returning its top-left corner. Returns
+    /// `None` if the rect can't fit in the atlas at all (too wide/tall, or the
+    /// atlas is full).
+    fn pack(&mut self, width: u16, height: u16) -> Option<(u16, u16)> {
+        if width > self.size || height > self.size {
+            return None;
+        }
+
+        if self.cursor_x + width > self.size {
+            self.cursor_x = 0;
+            self.cursor_y += self.shelf_height;
+            self.shelf_height = 0;
+        }
+
+        if self.cursor_y + height > self.size {
+            return None;
+        }
+
+        let pos = (self.cursor_x, self.cursor_y);
+        self.cursor_x += width;
+        self.shelf_height = self.shelf_height.max(height);
+
+        Some(pos)
+    }
+}
+
+/// A `.ttf`/`.otf` font that rasterizes glyphs on demand into a dynamically
+/// packed atlas texture, keyed by `(glyph_id, px_size)`.
+///
+/// Unlike `DrawContext::font_texture`, which only ever holds megaui's baked UI
+/// atlas, a `Font` grows its atlas as new glyph/size combinations are first
+/// requested, using a simple shelf packer.
+pub struct Font {
+    face: fontdue::Font,
+    atlas_data: Vec<u8>,
+    texture: Texture2D,
+    glyphs: std::collections::HashMap<(u16, u32), AtlasGlyph>,
+    packer: ShelfPacker,
+}
+
+impl Font {
+    pub fn load(ctx: &mut miniquad::Context, bytes: &[u8]) -> Font {
+        let face = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default())
+            .expect("invalid .ttf/.otf font data");
+        let atlas_data = vec![0u8; FONT_ATLAS_SIZE as usize * FONT_ATLAS_SIZE as usize * 4];
+        let texture = Texture2D::from_rgba8(ctx, FONT_ATLAS_SIZE, FONT_ATLAS_SIZE, &atlas_data);
+
+        Font {
+            face,
+            atlas_data,
+            texture,
+            glyphs: std::collections::HashMap::new(),
+            packer: ShelfPacker::new(FONT_ATLAS_SIZE),
+        }
+    }
+
+    /// Returns advance/line metrics for `text` set at `px_size`, without
+    /// touching the atlas (no rasterization needed for metrics alone).
+    pub fn measure_text(&self, text: &str, px_size: f32) -> TextMetrics {
+        let line_metrics =
+            self.face
+                .horizontal_line_metrics(px_size)
+                .unwrap_or(fontdue::LineMetrics {
+                    ascent: 0.,
+                    descent: 0.,
+                    line_gap: 0.,
+                    new_line_size: 0.,
+                });
+        let width = text
+            .chars()
+            .map(|c| self.face.metrics(c, px_size).advance_width)
+            .sum();
+
+        TextMetrics {
+            width,
+            ascent: line_metrics.ascent,
+            descent: line_metrics.descent,
+            line_gap: line_metrics.line_gap,
+        }
+    }
+
+    /// Returns the packed rect and placement metrics for `c` at `px_size`,
+    /// rasterizing and packing it into the atlas the first time it's seen.
+    fn glyph(&mut self, ctx: &mut miniquad::Context, c: char, px_size: f32) -> AtlasGlyph {
+        let glyph_id = self.face.lookup_glyph_index(c);
+        let key = (glyph_id, px_size.to_bits());
+
+        if let Some(&glyph) = self.glyphs.get(&key) {
+            return glyph;
+        }
+
+        let (metrics, bitmap) = self.face.rasterize_indexed(glyph_id, px_size);
+
+        // The atlas is a fixed FONT_ATLAS_SIZE square with no eviction, so once
+        // it fills up (e.g. several px sizes of a full alphabet) there's
+        // nowhere left to pack this glyph. Fail gracefully: skip rendering it
+        // rather than writing past `atlas_data`, but keep its real advance so
+        // text layout doesn't otherwise change.
+        let glyph = match self
+            .packer
+            .pack(metrics.width as u16, metrics.height as u16)
+        {
+            Some((atlas_x, atlas_y)) => {
+                for row in 0..metrics.height {
+                    for col in 0..metrics.width {
+                        let alpha = bitmap[row * metrics.width + col];
+                        let offset = ((atlas_y as usize + row) * FONT_ATLAS_SIZE as usize
+                            + (atlas_x as usize + col))
+                            * 4;
+                        self.atlas_data[offset..offset + 4]
+                            .copy_from_slice(&[255, 255, 255, alpha]);
+                    }
+                }
+                self.texture =
+                    Texture2D::from_rgba8(ctx, FONT_ATLAS_SIZE, FONT_ATLAS_SIZE, &self.atlas_data);
+
+                AtlasGlyph {
+                    atlas_x,
+                    atlas_y,
+                    width: metrics.width as u16,
+                    height: metrics.height as u16,
+                    xmin: metrics.xmin as f32,
+                    ymin: metrics.ymin as f32,
+                    advance: metrics.advance_width,
+                }
+            }
+            None => AtlasGlyph {
+                atlas_x: 0,
+                atlas_y: 0,
+                width: 0,
+                height: 0,
+                xmin: 0.,
+                ymin: 0.,
+                advance: metrics.advance_width,
+            },
+        };
+        self.glyphs.insert(key, glyph);
+
+        glyph
+    }
+}
+
+/// Per-texture policy for which level of a `MipChain` gets drawn (see
+/// `MipChain::level_for_scale`): `Nearest` always draws the base level, so
+/// pixel-art sprites stay crisp, `Linear` rounds down to the higher-resolution
+/// level and `Trilinear` rounds to the nearest level. Every mode draws from a
+/// single level; no mode blends two levels or sets the GPU sampler filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterMode {
+    Nearest,
+    Linear,
+    Trilinear,
+}
+
+/// One level of a box-filtered mip pyramid: raw RGBA8 bytes at half the
+/// resolution of the level before it.
+pub struct MipLevel {
+    pub width: u16,
+    pub height: u16,
+    pub data: Vec<u8>,
+}
+
+/// Box-downsamples a `width x height` RGBA8 image into a full mip pyramid,
+/// averaging each 2x2 texel block and halving width/height at every step
+/// until a 1x1 level is reached.
+pub fn generate_mipmaps(width: u16, height: u16, data: &[u8]) -> Vec<MipLevel> {
+    let mut levels = Vec::new();
+    let (mut w, mut h, mut pixels) = (width, height, data.to_vec());
+
+    while w > 1 || h > 1 {
+        let next_w = (w / 2).max(1);
+        let next_h = (h / 2).max(1);
+        let mut next_pixels = vec![0u8; next_w as usize * next_h as usize * 4];
+
+        for y in 0..next_h {
+            for x in 0..next_w {
+                let mut sum = [0u32; 4];
+                for dy in 0..2 {
+                    for dx in 0..2 {
+                        let sx = (x * 2 + dx).min(w - 1);
+                        let sy = (y * 2 + dy).min(h - 1);
+                        let offset = (sy as usize * w as usize + sx as usize) * 4;
+                        for (c, channel_sum) in sum.iter_mut().enumerate() {
+                            *channel_sum += pixels[offset + c] as u32;
+                        }
+                    }
+                }
+
+                let offset = (y as usize * next_w as usize + x as usize) * 4;
+                for (c, channel_sum) in sum.iter().enumerate() {
+                    next_pixels[offset + c] = (channel_sum / 4) as u8;
+                }
+            }
+        }
+
+        levels.push(MipLevel {
+            width: next_w,
+            height: next_h,
+            data: next_pixels.clone(),
+        });
+        w = next_w;
+        h = next_h;
+        pixels = next_pixels;
+    }
+
+    levels
+}
+
+/// A full mip chain, each level individually uploaded as its own GPU
+/// `Texture2D` (`levels[0]` is the base image).
+///
+/// The levels are separate textures: no multi-level texture object or
+/// mip-aware sampler state is set up, and levels are never blended.
+/// `DrawContext::draw_texture_mipmapped` avoids minification shimmer by
+/// picking one level with `level_for_scale`, following `filter`, and drawing
+/// from it.
+pub struct MipChain {
+    pub levels: Vec<Texture2D>,
+    pub filter: FilterMode,
+}
+
+impl MipChain {
+    /// Picks the mip level whose resolution best matches drawing the base
+    /// image at `scale` (1.0 = native size, 0.5 = drawn at half size, ...),
+    /// clamped to the levels actually generated.
+    ///
+    /// `self.filter` decides how the ideal (fractional) mip level rounds to
+    /// one actually on hand: `Nearest` always stays on the base level so
+    /// pixel-art sprites never pick up a blurrier mip, `Linear` rounds down
+    /// to the higher-resolution neighbor, and `Trilinear` rounds to the
+    /// nearest level; two levels are never blended.
+    pub fn level_for_scale(&self, scale: f32) -> &Texture2D {
+        let scale = scale.max(1. / (1 << 30) as f32);
+        let ideal = (-scale.log2()).max(0.);
+        let level = match self.filter {
+            FilterMode::Nearest => 0,
+            FilterMode::Linear => ideal.floor() as usize,
+            FilterMode::Trilinear => ideal.round() as usize,
+        };
+
+        &self.levels[level.min(self.levels.len() - 1)]
+    }
+}
+
+/// Extends `quad_gl::Texture2D` with mip-chain generation and a per-texture
+/// filter mode, so pixel-art sprites can keep crisp `Nearest` sampling while
+/// scaled sprites and distant atlas tiles get smooth minification.
+pub trait TextureMipmaps: Sized {
+    fn from_rgba8_with_mipmaps(
+        ctx: &mut miniquad::Context,
+        width: u16,
+        height: u16,
+        bytes: &[u8],
+        filter: FilterMode,
+    ) -> MipChain;
+}
+
+impl TextureMipmaps for Texture2D {
+    fn from_rgba8_with_mipmaps(
+        ctx: &mut miniquad::Context,
+        width: u16,
+        height: u16,
+        bytes: &[u8],
+        filter: FilterMode,
+    ) -> MipChain {
+        let mut levels = vec![Texture2D::from_rgba8(ctx, width, height, bytes)];
+        for mip in generate_mipmaps(width, height, bytes) {
+            levels.push(Texture2D::from_rgba8(ctx, mip.width, mip.height, &mip.data));
+        }
+
+        MipChain { levels, filter }
+    }
+}
+
+/// A stack of nested clip rects, each one intersected with its parent so a
+/// child clip can only ever shrink the visible area, never escape it.
+#[derive(Debug, Default)]
+struct ClipStack(Vec<Rect>);
+
+impl ClipStack {
+    /// Intersects `rect` with the current top (if any), pushes the result,
+    /// and returns it as the new top.
+    fn push(&mut self, rect: Rect) -> Rect {
+        let rect = match self.0.last() {
+            Some(&top) => top.intersect(rect),
+            None => rect,
+        };
+
+        self.0.push(rect);
+        rect
+    }
+
+    /// Pops the current top, returning whatever rect (if any) is now on top.
+    fn pop(&mut self) -> Option<Rect> {
+        self.0.pop();
+        self.top()
+    }
+
+    fn top(&self) -> Option<Rect> {
+        self.0.last().copied()
+    }
+}
+
 pub struct DrawContext {
     pub(crate) font_texture: Texture2D,
     pub(crate) gl: QuadGl,
     pub(crate) screen_coordinates: ScreenCoordinates,
     pub ui: megaui::Ui,
     ui_draw_list: Vec<megaui::DrawList>,
+    clip_stack: ClipStack,
 }
 
 impl DrawContext {
@@ -31,6 +610,7 @@ impl DrawContext {
             font_texture,
             ui,
             ui_draw_list: Vec::with_capacity(10000),
+            clip_stack: ClipStack::default(),
         };
 
         draw_context.update_projection_matrix(ctx);
@@ -61,6 +641,38 @@ impl DrawContext {
         self.gl.texture(None);
 
         std::mem::swap(&mut ui_draw_list, &mut self.ui_draw_list);
+
+        // The loop above drives the GL scissor directly from each UI draw
+        // command's own clip zone, leaving it on whatever the last command set.
+        // Restore it to `clip_stack`'s actual state so a stale UI clip rect
+        // doesn't leak into the next frame's application draw calls.
+        self.apply_clip();
+    }
+
+    /// Intersects `rect` with the currently active clip rect (if any) and pushes
+    /// the result as the new active scissor rect for subsequent draw calls.
+    ///
+    /// Mirrors the clipping zone that UI draw commands already carry, letting
+    /// application code clip its own `draw_rectangle`/`draw_texture` output to
+    /// build scrollable panels, masked sprites and minimaps.
+    pub fn push_clip_rect(&mut self, rect: Rect) {
+        self.clip_stack.push(rect);
+        self.apply_clip();
+    }
+
+    /// Pops the most recently pushed clip rect, restoring whatever clip (if any)
+    /// was active before it.
+    pub fn pop_clip_rect(&mut self) {
+        self.clip_stack.pop();
+        self.apply_clip();
+    }
+
+    fn apply_clip(&mut self) {
+        let scissor = self
+            .clip_stack
+            .top()
+            .map(|rect| (rect.x as i32, rect.y as i32, rect.w as i32, rect.h as i32));
+        self.gl.scissor(scissor);
     }
 
     pub fn draw_rectangle(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color) {
@@ -101,29 +713,102 @@ impl DrawContext {
         self.draw_rectangle(x, y + 1., 1., h - 2., color);
     }
 
-    /// Draw texture to x y w h position on the screen, using sx sy sw sh as a texture coordinates.
-    /// Good use example: drawing an image from texture atlas.
-    ///
-    /// TODO: maybe introduce Rect type?
-    pub fn draw_texture_rec(
+    /// Draw a rectangle with independently configurable corner radii, tessellating
+    /// each rounded corner as a quarter-circle arc of `segments` steps. The
+    /// whole outline is filled as a single triangle fan from the rectangle's
+    /// center; corners are not fanned from their own arc centers.
+    pub fn draw_rectangle_rounded(
         &mut self,
-        texture: Texture2D,
         x: f32,
         y: f32,
         w: f32,
         h: f32,
-        sx: f32,
-        sy: f32,
-        sw: f32,
-        sh: f32,
+        radii: Corners<f32>,
         color: Color,
+        segments: u32,
     ) {
+        let segments = segments.max(1);
+        let points = rounded_rectangle_outline(x, y, w, h, radii, segments);
+        let center = glam::Vec2::new(x + w / 2., y + h / 2.);
+
+        let mut vertices = Vec::with_capacity(points.len() + 1);
+        let mut indices = Vec::with_capacity(points.len() * 3);
+
+        vertices.push(Vertex::new(center.x, center.y, 0., 0.5, 0.5, color));
+        for point in &points {
+            vertices.push(Vertex::new(point.x, point.y, 0., 0., 0., color));
+        }
+
+        let n = points.len() as u16;
+        for i in 0..n {
+            indices.extend_from_slice(&[0, i + 1, (i + 1) % n + 1]);
+        }
+
+        self.gl.texture(None);
+        self.gl.geometry(&vertices, &indices);
+    }
+
+    /// Outline-only version of `draw_rectangle_rounded`, drawn as a loop of
+    /// `draw_line` segments around the rounded perimeter.
+    pub fn draw_rectangle_rounded_lines(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radii: Corners<f32>,
+        thickness: f32,
+        color: Color,
+        segments: u32,
+    ) {
+        let segments = segments.max(1);
+        let points = rounded_rectangle_outline(x, y, w, h, radii, segments);
+
+        let n = points.len();
+        for i in 0..n {
+            let a = points[i];
+            let b = points[(i + 1) % n];
+            self.draw_line(a.x, a.y, b.x, b.y, thickness, color);
+        }
+    }
+
+    /// Draws `texture` to the `dest` rect on screen, using `source` as texture
+    /// coordinates (in pixels). Good use example: drawing an image from a
+    /// texture atlas.
+    pub fn draw_texture_rec(&mut self, texture: Texture2D, dest: Rect, source: Rect, color: Color) {
+        #[rustfmt::skip]
+        let vertices = [
+            Vertex::new(dest.x         , dest.y         , 0.,  source.x             /texture.width(),  source.y             /texture.height(), color),
+            Vertex::new(dest.x + dest.w, dest.y         , 0., (source.x + source.w) /texture.width(),  source.y             /texture.height(), color),
+            Vertex::new(dest.x + dest.w, dest.y + dest.h, 0., (source.x + source.w) /texture.width(), (source.y + source.h) /texture.height(), color),
+            Vertex::new(dest.x         , dest.y + dest.h, 0.,  source.x             /texture.width(), (source.y + source.h) /texture.height(), color),
+        ];
+        let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
+
+        self.gl.texture(Some(texture));
+        self.gl.geometry(&vertices, &indices);
+    }
+
+    /// Draws `mips` to the `dest` rect on screen, picking whichever level of
+    /// the chain best matches `dest`'s size relative to the base image
+    /// (following `mips.filter`) instead of always sampling the full-resolution
+    /// level — this is what actually avoids minification shimmer; sampling
+    /// `mips.levels[0]` directly with `draw_texture` does not.
+    pub fn draw_texture_mipmapped(&mut self, mips: &MipChain, dest: Rect, color: Color) {
+        let base = &mips.levels[0];
+        let scale = if base.width() > 0. {
+            dest.w / base.width()
+        } else {
+            1.
+        };
+        let texture = *mips.level_for_scale(scale);
+
         #[rustfmt::skip]
         let vertices = [
-            Vertex::new(x    , y    , 0.,  sx      /texture.width(),  sy      /texture.height(), color),
-            Vertex::new(x + w, y    , 0., (sx + sw)/texture.width(),  sy      /texture.height(), color),
-            Vertex::new(x + w, y + h, 0., (sx + sw)/texture.width(), (sy + sh)/texture.height(), color),
-            Vertex::new(x    , y + h, 0.,  sx      /texture.width(), (sy + sh)/texture.height(), color),
+            Vertex::new(dest.x         , dest.y         , 0., 0.0, 0.0, color),
+            Vertex::new(dest.x + dest.w, dest.y         , 0., 1.0, 0.0, color),
+            Vertex::new(dest.x + dest.w, dest.y + dest.h, 0., 1.0, 1.0, color),
+            Vertex::new(dest.x         , dest.y + dest.h, 0., 0.0, 1.0, color),
         ];
         let indices: [u16; 6] = [0, 1, 2, 0, 2, 3];
 
@@ -131,6 +816,70 @@ impl DrawContext {
         self.gl.geometry(&vertices, &indices);
     }
 
+    /// Float-argument form of `draw_texture_rec`, taking the same arguments
+    /// (`x, y, w, h` for the destination, `sx, sy, sw, sh` for the source) as
+    /// the float-based `draw_texture_rec` did. It has its own name, so callers
+    /// of that old signature have to switch to `draw_texture_rec_xywh`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_texture_rec_xywh(
+        &mut self,
+        texture: Texture2D,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        sx: f32,
+        sy: f32,
+        sw: f32,
+        sh: f32,
+        color: Color,
+    ) {
+        self.draw_texture_rec(
+            texture,
+            Rect::new(x, y, w, h),
+            Rect::new(sx, sy, sw, sh),
+            color,
+        )
+    }
+
+    /// Draws `text` with `font` along a baseline anchored at `(x, y)`, packing
+    /// any glyph not already in the atlas into it on first use.
+    pub fn draw_text(
+        &mut self,
+        ctx: &mut miniquad::Context,
+        text: &str,
+        x: f32,
+        y: f32,
+        font: &mut Font,
+        px_size: f32,
+        color: Color,
+    ) {
+        let mut cursor_x = x;
+
+        for c in text.chars() {
+            let glyph = font.glyph(ctx, c, px_size);
+
+            if glyph.width > 0 && glyph.height > 0 {
+                let dest = Rect::new(
+                    cursor_x + glyph.xmin,
+                    y - glyph.ymin - glyph.height as f32,
+                    glyph.width as f32,
+                    glyph.height as f32,
+                );
+                let source = Rect::new(
+                    glyph.atlas_x as f32,
+                    glyph.atlas_y as f32,
+                    glyph.width as f32,
+                    glyph.height as f32,
+                );
+
+                self.draw_texture_rec(font.texture, dest, source, color);
+            }
+
+            cursor_x += glyph.advance;
+        }
+    }
+
     pub fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color) {
         let dx = x2 - x1;
         let dy = y2 - y1;
@@ -156,23 +905,141 @@ impl DrawContext {
         );
     }
 
+    /// Builds the rim points of a regular polygon with `sides` sides, centered
+    /// at `(x, y)` with the given `radius` and `rotation` (radians), shared by
+    /// `draw_poly` and `draw_circle_gradient`.
+    fn regular_polygon_points(
+        x: f32,
+        y: f32,
+        sides: u32,
+        radius: f32,
+        rotation: f32,
+    ) -> Vec<glam::Vec2> {
+        let mut points = Vec::with_capacity(sides as usize + 1);
+
+        for i in 0..=sides {
+            let angle = rotation + i as f32 / sides as f32 * std::f32::consts::PI * 2.;
+            points.push(glam::Vec2::new(
+                x + radius * angle.cos(),
+                y + radius * angle.sin(),
+            ));
+        }
+
+        points
+    }
+
+    /// Draws a filled fan of triangles from `center` to each consecutive pair of
+    /// `points`, coloring every vertex with `color_at`. Shared by
+    /// `draw_triangle_fan` (a constant color) and `draw_circle_gradient`
+    /// (sampling a `Gradient` per vertex).
+    fn fill_triangle_fan(
+        &mut self,
+        center: glam::Vec2,
+        points: &[glam::Vec2],
+        mut color_at: impl FnMut(glam::Vec2) -> Color,
+    ) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let mut vertices = Vec::with_capacity(points.len() + 1);
+        let mut indices = Vec::with_capacity(points.len() * 3);
+
+        vertices.push(Vertex::new(
+            center.x,
+            center.y,
+            0.,
+            0.,
+            0.,
+            color_at(center),
+        ));
+        for point in points {
+            vertices.push(Vertex::new(point.x, point.y, 0., 0., 0., color_at(*point)));
+        }
+
+        for i in 0..points.len() as u16 - 1 {
+            indices.extend_from_slice(&[0, i + 1, i + 2]);
+        }
+
+        self.gl.texture(None);
+        self.gl.geometry(&vertices, &indices);
+    }
+
+    /// Draws a filled fan of triangles from `center` to each consecutive pair of
+    /// `points`, i.e. the same fan topology `draw_circle` and `draw_poly` build
+    /// around their rim vertices.
+    pub fn draw_triangle_fan(&mut self, center: glam::Vec2, points: &[glam::Vec2], color: Color) {
+        self.fill_triangle_fan(center, points, |_| color);
+    }
+
+    /// Draws a regular polygon with `sides` sides, centered at `(x, y)` with the
+    /// given `radius` and `rotation` (radians), as a triangle fan.
+    pub fn draw_poly(
+        &mut self,
+        x: f32,
+        y: f32,
+        sides: u32,
+        radius: f32,
+        rotation: f32,
+        color: Color,
+    ) {
+        let sides = sides.max(3);
+        let points = Self::regular_polygon_points(x, y, sides, radius, rotation);
+
+        self.draw_triangle_fan(glam::Vec2::new(x, y), &points, color);
+    }
+
     pub fn draw_circle(&mut self, x: f32, y: f32, r: f32, color: Color) {
         const NUM_DIVISIONS: u32 = 20;
 
+        self.draw_poly(x, y, NUM_DIVISIONS, r, 0., color);
+    }
+
+    /// Draw a rectangle filled with `gradient`, evaluating it at the corners of an
+    /// `subdivisions x subdivisions` grid so long linear gradients don't band.
+    pub fn draw_rectangle_gradient(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        gradient: &Gradient,
+        subdivisions: u32,
+    ) {
+        let subdivisions = subdivisions.max(1);
+        let stride = subdivisions + 1;
+
         let mut vertices = Vec::<Vertex>::new();
         let mut indices = Vec::<u16>::new();
 
-        vertices.push(Vertex::new(x, y, 0., 0., 0., color));
-        for i in 0..NUM_DIVISIONS + 1 {
-            let rx = (i as f32 / NUM_DIVISIONS as f32 * std::f32::consts::PI * 2.).cos();
-            let ry = (i as f32 / NUM_DIVISIONS as f32 * std::f32::consts::PI * 2.).sin();
-
-            let vertex = Vertex::new(x + r * rx, y + r * ry, 0., rx, ry, color);
+        for row in 0..=subdivisions {
+            for col in 0..=subdivisions {
+                let u = col as f32 / subdivisions as f32;
+                let v = row as f32 / subdivisions as f32;
+                let point = glam::Vec2::new(x + w * u, y + h * v);
 
-            vertices.push(vertex);
+                vertices.push(Vertex::new(
+                    point.x,
+                    point.y,
+                    0.,
+                    u,
+                    v,
+                    gradient.color_at(point),
+                ));
+            }
+        }
 
-            if i != NUM_DIVISIONS {
-                indices.extend_from_slice(&[0, i as u16 + 1, i as u16 + 2]);
+        for row in 0..subdivisions {
+            for col in 0..subdivisions {
+                let i = row * stride + col;
+                indices.extend_from_slice(&[
+                    i as u16,
+                    (i + 1) as u16,
+                    (i + stride) as u16,
+                    (i + 1) as u16,
+                    (i + stride + 1) as u16,
+                    (i + stride) as u16,
+                ]);
             }
         }
 
@@ -180,6 +1047,17 @@ impl DrawContext {
         self.gl.geometry(&vertices, &indices);
     }
 
+    /// Draw a circle filled with `gradient`, evaluating it at the center and each
+    /// rim vertex of the same triangle fan `draw_circle` builds.
+    pub fn draw_circle_gradient(&mut self, x: f32, y: f32, r: f32, gradient: &Gradient) {
+        const NUM_DIVISIONS: u32 = 20;
+
+        let points = Self::regular_polygon_points(x, y, NUM_DIVISIONS, r, 0.);
+        self.fill_triangle_fan(glam::Vec2::new(x, y), &points, |point| {
+            gradient.color_at(point)
+        });
+    }
+
     pub(crate) fn perform_render_passes(&mut self, ctx: &mut miniquad::Context) {
         self.draw_ui(ctx);
         self.gl.draw(ctx);
@@ -192,11 +1070,290 @@ impl DrawContext {
             ScreenCoordinates::PixelPerfect => {
                 glam::Mat4::orthographic_rh_gl(0., width, height, 0., -1., 1.)
             }
-            ScreenCoordinates::Fixed(left, right, bottom, top) => {
-                glam::Mat4::orthographic_rh_gl(left, right, bottom, top, -1., 1.)
-            }
+            ScreenCoordinates::Fixed(rect) => glam::Mat4::orthographic_rh_gl(
+                rect.x,
+                rect.x + rect.w,
+                rect.y + rect.h,
+                rect.y,
+                -1.,
+                1.,
+            ),
         };
 
         self.gl.set_projection_matrix(projection);
     }
 }
+
+#[cfg(test)]
+mod clip_stack_tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_stack_has_no_top() {
+        assert_eq!(ClipStack::default().top(), None);
+    }
+
+    #[test]
+    fn pushing_onto_an_empty_stack_keeps_the_rect_as_is() {
+        let mut stack = ClipStack::default();
+
+        assert_eq!(
+            stack.push(Rect::new(1., 2., 3., 4.)),
+            Rect::new(1., 2., 3., 4.)
+        );
+    }
+
+    #[test]
+    fn a_nested_push_is_intersected_with_the_current_top() {
+        let mut stack = ClipStack::default();
+        stack.push(Rect::new(0., 0., 10., 10.));
+
+        let nested = stack.push(Rect::new(5., 5., 10., 10.));
+
+        assert_eq!(nested, Rect::new(5., 5., 5., 5.));
+        assert_eq!(stack.top(), Some(nested));
+    }
+
+    #[test]
+    fn popping_restores_the_prior_top() {
+        let mut stack = ClipStack::default();
+        let outer = stack.push(Rect::new(0., 0., 10., 10.));
+        stack.push(Rect::new(5., 5., 10., 10.));
+
+        assert_eq!(stack.pop(), Some(outer));
+        assert_eq!(stack.pop(), None);
+    }
+}
+
+#[cfg(test)]
+mod shelf_packer_tests {
+    use super::*;
+
+    #[test]
+    fn packs_left_to_right_along_a_shelf() {
+        let mut packer = ShelfPacker::new(16);
+
+        assert_eq!(packer.pack(4, 4), Some((0, 0)));
+        assert_eq!(packer.pack(4, 4), Some((4, 0)));
+    }
+
+    #[test]
+    fn starts_a_new_shelf_once_the_row_runs_out_of_width() {
+        let mut packer = ShelfPacker::new(8);
+
+        assert_eq!(packer.pack(5, 3), Some((0, 0)));
+        assert_eq!(packer.pack(5, 2), Some((0, 3)));
+    }
+
+    #[test]
+    fn returns_none_for_a_rect_wider_or_taller_than_the_atlas() {
+        let mut packer = ShelfPacker::new(8);
+
+        assert_eq!(packer.pack(9, 1), None);
+        assert_eq!(packer.pack(1, 9), None);
+    }
+
+    #[test]
+    fn returns_none_once_the_atlas_is_full() {
+        let mut packer = ShelfPacker::new(4);
+
+        assert_eq!(packer.pack(4, 4), Some((0, 0)));
+        assert_eq!(packer.pack(1, 1), None);
+    }
+}
+
+#[cfg(test)]
+mod rect_tests {
+    use super::*;
+
+    #[test]
+    fn contains_includes_the_edges() {
+        let rect = Rect::new(0., 0., 10., 10.);
+
+        assert!(rect.contains(glam::Vec2::new(0., 0.)));
+        assert!(rect.contains(glam::Vec2::new(10., 10.)));
+        assert!(!rect.contains(glam::Vec2::new(10.1, 5.)));
+    }
+
+    #[test]
+    fn overlaps_is_true_for_a_partial_overlap() {
+        let a = Rect::new(0., 0., 10., 10.);
+        let b = Rect::new(5., 5., 10., 10.);
+
+        assert!(a.overlaps(b));
+        assert!(b.overlaps(a));
+    }
+
+    #[test]
+    fn overlaps_is_false_for_rects_that_only_touch_at_an_edge() {
+        let a = Rect::new(0., 0., 10., 10.);
+        let b = Rect::new(10., 0., 10., 10.);
+
+        assert!(!a.overlaps(b));
+    }
+
+    #[test]
+    fn intersect_of_overlapping_rects_is_their_shared_region() {
+        let a = Rect::new(0., 0., 10., 10.);
+        let b = Rect::new(5., 5., 10., 10.);
+
+        assert_eq!(a.intersect(b), Rect::new(5., 5., 5., 5.));
+    }
+
+    #[test]
+    fn intersect_of_non_overlapping_rects_is_zero_sized() {
+        let a = Rect::new(0., 0., 10., 10.);
+        let b = Rect::new(20., 20., 10., 10.);
+
+        let result = a.intersect(b);
+        assert_eq!(result.w, 0.);
+        assert_eq!(result.h, 0.);
+    }
+}
+
+#[cfg(test)]
+mod rounded_rectangle_tests {
+    use super::*;
+
+    #[test]
+    fn radii_larger_than_half_the_rect_are_clamped_to_its_bounds() {
+        let points = rounded_rectangle_outline(0., 0., 10., 20., Corners::all(100.), 4);
+
+        for point in &points {
+            assert!((0. ..=10.).contains(&point.x));
+            assert!((0. ..=20.).contains(&point.y));
+        }
+    }
+
+    #[test]
+    fn a_near_zero_radius_corner_degenerates_to_a_sharp_point() {
+        let radii = Corners {
+            top_left: 0.,
+            top_right: 5.,
+            bottom_right: 5.,
+            bottom_left: 5.,
+        };
+        let points = rounded_rectangle_outline(0., 0., 20., 20., radii, 8);
+
+        assert_eq!(points[0], glam::Vec2::new(0., 0.));
+    }
+
+    #[test]
+    fn a_rounded_corner_emits_segments_plus_one_points() {
+        let points = rounded_rectangle_outline(0., 0., 20., 20., Corners::all(5.), 8);
+
+        assert_eq!(points.len(), 4 * (8 + 1));
+    }
+
+    #[test]
+    fn negative_width_or_height_does_not_panic() {
+        rounded_rectangle_outline(0., 0., -10., 20., Corners::all(5.), 8);
+        rounded_rectangle_outline(0., 0., 10., -20., Corners::all(5.), 8);
+        rounded_rectangle_outline(0., 0., -10., -20., Corners::all(5.), 8);
+    }
+}
+
+#[cfg(test)]
+mod gradient_tests {
+    use super::*;
+
+    #[test]
+    fn sample_stops_returns_endpoint_colors_exactly() {
+        let stops = [(0., Color([1., 0., 0., 1.])), (1., Color([0., 0., 1., 1.]))];
+
+        assert_eq!(sample_stops(&stops, 0.), stops[0].1);
+        assert_eq!(sample_stops(&stops, 1.), stops[1].1);
+    }
+
+    #[test]
+    fn sample_stops_lerps_opaque_colors_linearly() {
+        let stops = [(0., Color([0., 0., 0., 1.])), (1., Color([1., 1., 1., 1.]))];
+
+        assert_eq!(sample_stops(&stops, 0.5), Color([0.5, 0.5, 0.5, 1.]));
+    }
+
+    #[test]
+    fn sample_stops_clamps_out_of_range_t() {
+        let stops = [(0., Color([1., 0., 0., 1.])), (1., Color([0., 0., 1., 1.]))];
+
+        assert_eq!(sample_stops(&stops, -1.), stops[0].1);
+        assert_eq!(sample_stops(&stops, 2.), stops[1].1);
+    }
+
+    #[test]
+    fn sample_stops_of_a_single_stop_ignores_t() {
+        let stops = [(0.5, Color([0.2, 0.4, 0.6, 1.]))];
+
+        assert_eq!(sample_stops(&stops, 0.), stops[0].1);
+        assert_eq!(sample_stops(&stops, 1.), stops[0].1);
+    }
+
+    #[test]
+    fn premultiply_unpremultiply_round_trips_opaque_colors() {
+        let color = Color([0.2, 0.4, 0.6, 1.]);
+
+        assert_eq!(unpremultiply(premultiply(color)), color);
+    }
+
+    #[test]
+    fn premultiply_scales_channels_by_alpha() {
+        let color = Color([1., 0.5, 0., 0.5]);
+
+        assert_eq!(premultiply(color), [0.5, 0.25, 0., 0.5]);
+    }
+
+    #[test]
+    fn unpremultiply_of_fully_transparent_is_transparent_black() {
+        assert_eq!(unpremultiply([0.3, 0.2, 0.1, 0.]), Color([0., 0., 0., 0.]));
+    }
+}
+
+#[cfg(test)]
+mod mipmap_tests {
+    use super::*;
+
+    #[test]
+    fn pyramid_halves_dimensions_down_to_1x1() {
+        let base = vec![255u8; 8 * 4 * 4];
+        let levels = generate_mipmaps(8, 4, &base);
+
+        let dims: Vec<(u16, u16)> = levels
+            .iter()
+            .map(|level| (level.width, level.height))
+            .collect();
+        assert_eq!(dims, vec![(4, 2), (2, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn pyramid_of_odd_dimensions_still_reaches_1x1() {
+        let base = vec![128u8; 5 * 3 * 4];
+        let levels = generate_mipmaps(5, 3, &base);
+
+        assert_eq!(levels.last().unwrap().width, 1);
+        assert_eq!(levels.last().unwrap().height, 1);
+        for level in &levels {
+            assert_eq!(
+                level.data.len(),
+                level.width as usize * level.height as usize * 4
+            );
+        }
+    }
+
+    #[test]
+    fn pyramid_of_a_1x1_image_is_empty() {
+        assert!(generate_mipmaps(1, 1, &[10, 20, 30, 40]).is_empty());
+    }
+
+    #[test]
+    fn box_filter_averages_a_uniform_color_exactly() {
+        let mut base = Vec::new();
+        for _ in 0..(4 * 4) {
+            base.extend_from_slice(&[40, 80, 120, 160]);
+        }
+        let levels = generate_mipmaps(4, 4, &base);
+
+        for pixel in levels[0].data.chunks_exact(4) {
+            assert_eq!(pixel, &[40, 80, 120, 160]);
+        }
+    }
+}